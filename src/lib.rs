@@ -41,6 +41,275 @@
 //! }
 //! ```
 //!
+//! ## RFC 7807 `application/problem+json`
+//!
+//! By default the derived response body is an ad-hoc `{"code", "error"}` JSON object. If your API
+//! has standardized on [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) Problem Details, opt in
+//! with a container attribute:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//! use axum::http::StatusCode;
+//!
+//! #[derive(ErrorResponse)]
+//! #[error_response(format = "problem+json")]
+//! pub enum CreateUserError {
+//!     #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+//!     #[problem(type = "https://example.com/errors/invalid-body", title = "Invalid body")]
+//!     InvalidBody(String),
+//! }
+//! # impl std::error::Error for CreateUserError {}
+//! # impl std::fmt::Display for CreateUserError {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "invalid body") }
+//! # }
+//! ```
+//!
+//! This serializes `type` (defaulting to `about:blank`), `title` (defaulting to the status code's
+//! canonical reason phrase), `status`, and `detail` (the `Display` string for client-facing 4xx
+//! responses, suppressed for 5xx), and sets `Content-Type: application/problem+json`.
+//!
+//! ## Structs
+//!
+//! `ErrorResponse` can also be derived for named, tuple, and unit structs, which is the shape
+//! most thiserror error types take when there's only one failure case. A top-level `#[status(...)]`
+//! attribute sets the status (defaulting to 500), exactly as it does per-variant on an enum:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//! use axum::http::StatusCode;
+//!
+//! #[derive(ErrorResponse)]
+//! #[status(StatusCode::NOT_FOUND)]
+//! pub struct UserNotFound(pub uuid::Uuid);
+//!
+//! impl std::error::Error for UserNotFound {}
+//! impl std::fmt::Display for UserNotFound {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write!(f, "no user with id {}", self.0)
+//!     }
+//! }
+//! ```
+//!
+//! ## `#[from]`
+//!
+//! Mark a variant's (or struct's) single field `#[from]` to get an `impl From<SourceError>` for
+//! free, plus an `Error::source()` that returns it, so `?` works without any hand-written
+//! plumbing:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//!
+//! #[derive(ErrorResponse)]
+//! pub enum CreateUserError {
+//!     InsertUserToDb(#[from] sqlx::Error),
+//! }
+//!
+//! impl std::fmt::Display for CreateUserError {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         match self {
+//!             Self::InsertUserToDb(_) => write!(f, "failed to insert user into the database"),
+//!         }
+//!     }
+//! }
+//!
+//! // async fn create_user() -> Result<(), CreateUserError> {
+//! //     sqlx::query("...").execute(&pool).await?;
+//! //     Ok(())
+//! // }
+//! ```
+//!
+//! `#[from]` takes over the whole `impl Error for #ident`, so once any variant (or a struct's
+//! single field) uses it, there's no room left for a hand-written `impl Error` alongside it —
+//! attempting one is a conflicting-impl error. A variant that needs a source without also
+//! wanting a `From` conversion can mark a field `#[source]` instead; it's folded into the same
+//! `source()` match, and unlike `#[from]` it's allowed alongside other fields in the variant:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//!
+//! #[derive(ErrorResponse)]
+//! pub enum CreateUserError {
+//!     InsertUserToDb(#[from] sqlx::Error),
+//!     ValidationFailed {
+//!         field: String,
+//!         #[source]
+//!         cause: std::num::ParseIntError,
+//!     },
+//! }
+//!
+//! impl std::fmt::Display for CreateUserError {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         match self {
+//!             Self::InsertUserToDb(_) => write!(f, "failed to insert user into the database"),
+//!             Self::ValidationFailed { field, .. } => write!(f, "invalid value for {field}"),
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! A variant with neither attribute still compiles, but its `source()` falls through to the
+//! derive's blanket `None` — there's no way left to hand-write one in for just that variant.
+//!
+//! ## `#[public("...")]`
+//!
+//! By default a 4xx response shows the client the variant's `Display` string, while a 5xx hides
+//! it behind "Internal server error". Sometimes a 4xx's `Display` still contains detail (a failed
+//! query, a file path) you want logged but not returned to the client. `#[public("...")]` gives
+//! that variant its own client-facing message, independent of `Display`, for any status class; the
+//! full `Display`/source chain is still sent to `tracing` whenever a `#[public(...)]` message is
+//! used, even for a 4xx that wouldn't otherwise be logged (unless `#[log(level = "off")]`, below,
+//! turns logging off for it). The literal is a format string that can
+//! refer to the variant's fields by name — tuple fields are named `field0`, `field1`, ...:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//! use axum::http::StatusCode;
+//!
+//! #[derive(ErrorResponse)]
+//! pub enum CreateUserError {
+//!     #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+//!     #[public("could not create user, please check your input and try again")]
+//!     InsertUserToDb(sqlx::Error),
+//! }
+//! # impl std::error::Error for CreateUserError {}
+//! # impl std::fmt::Display for CreateUserError {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//! #         write!(f, "failed to insert user into the database")
+//! #     }
+//! # }
+//! ```
+//!
+//! ## `#[header("Name" = "value")]`
+//!
+//! Auth and rate-limit errors routinely need to set a response header alongside the status — a
+//! 401 with `WWW-Authenticate`, a 429 with `Retry-After`. `#[header(...)]` is repeatable on a
+//! variant (or the container, for a struct) and merges its headers into the final response. Like
+//! `#[public(...)]`, the value is a format string that can interpolate the fields:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//! use axum::http::StatusCode;
+//!
+//! #[derive(ErrorResponse)]
+//! pub enum AuthError {
+//!     #[status(StatusCode::UNAUTHORIZED)]
+//!     #[header("WWW-Authenticate" = "Bearer")]
+//!     MissingToken,
+//!
+//!     #[status(StatusCode::TOO_MANY_REQUESTS)]
+//!     #[header("Retry-After" = "{field0}")]
+//!     RateLimited(u64),
+//! }
+//! # impl std::error::Error for AuthError {}
+//! # impl std::fmt::Display for AuthError {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//! #         write!(f, "auth error")
+//! #     }
+//! # }
+//!
+//! use axum::response::IntoResponse;
+//! let response = AuthError::MissingToken.into_response();
+//! assert_eq!(response.headers().get("www-authenticate").unwrap(), "Bearer");
+//! ```
+//!
+//! An invalid header name or value (e.g. a name containing a space, or a value with a stray
+//! control character) is dropped rather than panicking the response. Names are matched
+//! case-insensitively, so `"X-Ok"` is fine:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//! use axum::{http::StatusCode, response::IntoResponse};
+//!
+//! #[derive(ErrorResponse)]
+//! pub enum WebhookError {
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[header("not a header" = "value")]
+//!     #[header("X-Reason" = "bad payload\n")]
+//!     #[header("X-Ok" = "still set")]
+//!     InvalidPayload,
+//! }
+//! # impl std::error::Error for WebhookError {}
+//! # impl std::fmt::Display for WebhookError {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//! #         write!(f, "invalid payload")
+//! #     }
+//! # }
+//!
+//! let response = WebhookError::InvalidPayload.into_response();
+//! assert!(response.headers().get("not a header").is_none());
+//! assert!(response.headers().get("x-reason").is_none());
+//! assert_eq!(response.headers().get("x-ok").unwrap(), "still set");
+//! ```
+//!
+//! ## `#[log(level = "...")]`
+//!
+//! By default a variant is logged through `tracing::error!` only when it's a 5xx (or when
+//! `#[public(...)]` is hiding detail from the client). A container or per-variant
+//! `#[log(level = "...")]` attribute overrides that: `"off"` never logs, and any of
+//! `"error"`/`"warn"`/`"info"`/`"debug"`/`"trace"` always logs at that level, even for a 4xx. A
+//! variant-level attribute wins over a container-level one. The log event carries the `Display`
+//! message plus `error_details`, the same `Debug` representation that walks the full
+//! `Caused by:` source chain:
+//!
+//! ```rust
+//! use axum_derive_error::ErrorResponse;
+//! use axum::http::StatusCode;
+//!
+//! #[derive(ErrorResponse)]
+//! pub enum LoginError {
+//!     /// Failed logins are routine, but operators still want a low-noise trail of them.
+//!     #[status(StatusCode::UNAUTHORIZED)]
+//!     #[log(level = "info")]
+//!     InvalidCredentials,
+//!
+//!     /// Never worth logging - the client already gets the full message.
+//!     #[status(StatusCode::BAD_REQUEST)]
+//!     #[log(level = "off")]
+//!     InvalidBody(String),
+//! }
+//! # impl std::error::Error for LoginError {}
+//! # impl std::fmt::Display for LoginError {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//! #         write!(f, "login error")
+//! #     }
+//! # }
+//!
+//! use std::sync::{
+//!     atomic::{AtomicUsize, Ordering},
+//!     Arc,
+//! };
+//! use axum::response::IntoResponse;
+//!
+//! // A minimal `Subscriber` that just counts events, to confirm whether a variant logged.
+//! struct CountingSubscriber(Arc<AtomicUsize>);
+//! impl tracing::Subscriber for CountingSubscriber {
+//!     fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+//!         true
+//!     }
+//!     fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+//!         tracing::span::Id::from_u64(1)
+//!     }
+//!     fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+//!     fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+//!     fn event(&self, _: &tracing::Event<'_>) {
+//!         self.0.fetch_add(1, Ordering::SeqCst);
+//!     }
+//!     fn enter(&self, _: &tracing::span::Id) {}
+//!     fn exit(&self, _: &tracing::span::Id) {}
+//! }
+//!
+//! let count = Arc::new(AtomicUsize::new(0));
+//! tracing::subscriber::with_default(CountingSubscriber(count.clone()), || {
+//!     let _ = LoginError::InvalidBody("bad request".to_string()).into_response();
+//! });
+//! assert_eq!(count.load(Ordering::SeqCst), 0, "#[log(level = \"off\")] must not log");
+//!
+//! tracing::subscriber::with_default(CountingSubscriber(count.clone()), || {
+//!     let _ = LoginError::InvalidCredentials.into_response();
+//! });
+//! assert_eq!(count.load(Ordering::SeqCst), 1, "an explicit level must still log");
+//! ```
+//!
 //! ## License
 //!
 //! Licensed under either of
@@ -60,80 +329,765 @@
 
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{parse_macro_input, Data, DataEnum, DeriveInput};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, Data, DataEnum, DeriveInput, LitStr, MetaNameValue,
+    Token,
+};
+
+/// The body format the derived `into_response` should emit.
+enum ResponseFormat {
+    /// The original ad-hoc `{"code", "error"}` JSON body.
+    Default,
+    /// RFC 7807 `application/problem+json`.
+    ProblemJson,
+}
+
+/// Parsed `#[error_response(...)]` container attribute.
+#[derive(Default)]
+struct ErrorResponseAttr {
+    format: Option<LitStr>,
+}
+
+impl syn::parse::Parse for ErrorResponseAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let metas = Punctuated::<MetaNameValue, Token![,]>::parse_terminated(input)?;
+        let mut attr = Self::default();
+        for meta in metas {
+            if meta.path.is_ident("format") {
+                if let syn::Lit::Str(lit) = meta.lit {
+                    attr.format = Some(lit);
+                }
+            }
+        }
+        Ok(attr)
+    }
+}
+
+/// Parsed `#[problem(...)]` per-variant attribute.
+#[derive(Default)]
+struct ProblemAttr {
+    type_uri: Option<LitStr>,
+    title: Option<LitStr>,
+}
+
+impl syn::parse::Parse for ProblemAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let metas = Punctuated::<MetaNameValue, Token![,]>::parse_terminated(input)?;
+        let mut attr = Self::default();
+        for meta in metas {
+            if meta.path.is_ident("type") {
+                if let syn::Lit::Str(lit) = meta.lit {
+                    attr.type_uri = Some(lit);
+                }
+            } else if meta.path.is_ident("title") {
+                if let syn::Lit::Str(lit) = meta.lit {
+                    attr.title = Some(lit);
+                }
+            }
+        }
+        Ok(attr)
+    }
+}
+
+fn find_attr<'a>(attrs: &'a [syn::Attribute], name: &str) -> Option<&'a syn::Attribute> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.get_ident().map(|i| *i == name).unwrap_or(false))
+}
+
+fn parse_response_format(attrs: &[syn::Attribute]) -> ResponseFormat {
+    let Some(attr) = find_attr(attrs, "error_response") else {
+        return ResponseFormat::Default;
+    };
+    let parsed: ErrorResponseAttr = attr
+        .parse_args()
+        .expect("invalid #[error_response(...)] attribute");
+    match parsed.format {
+        Some(format) if format.value() == "problem+json" => ResponseFormat::ProblemJson,
+        Some(format) => panic!(
+            "unsupported #[error_response(format = \"{}\")], expected \"problem+json\"",
+            format.value()
+        ),
+        None => ResponseFormat::Default,
+    }
+}
 
-#[proc_macro_derive(ErrorResponse, attributes(status))]
+#[proc_macro_derive(
+    ErrorResponse,
+    attributes(status, error_response, problem, from, public, header, log, source)
+)]
 pub fn derive_error_response(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(tokens as DeriveInput);
     let ident = input.ident;
+    let format = parse_response_format(&input.attrs);
+    let container_log_level = log_level_from_attrs(&input.attrs);
 
     match input.data {
         Data::Union(_) => panic!("cannot derive ErrorResponse for unions"),
-        Data::Struct(_) => panic!("cannot derive ErrorResponse for structs yet"),
-        Data::Enum(enum_data) => derive_error_response_for_enum(ident, enum_data).into(),
-    }
-}
-
-fn derive_error_response_for_enum(ident: Ident, enum_data: DataEnum) -> TokenStream {
-    let status_codes = enum_data.variants.into_iter().map(|variant| {
-        let variant_name = variant.ident;
-        let attr = variant.attrs.into_iter().find(|attr| {
-            attr.path
-                .get_ident()
-                .map(|ident| *ident == "status")
-                .unwrap_or_default()
-        });
-        let match_fields = match variant.fields {
-            syn::Fields::Named(_) => quote!({..}),
-            syn::Fields::Unnamed(fields) => {
-                let fields = fields.unnamed.into_iter().map(|_| quote!(_));
-                quote!{
-                    (#(#fields,)*)
+        Data::Struct(data) => derive_error_response_for_struct(
+            ident,
+            input.attrs,
+            data.fields,
+            format,
+            container_log_level,
+        )
+        .into(),
+        Data::Enum(enum_data) => {
+            derive_error_response_for_enum(ident, enum_data, format, container_log_level).into()
+        }
+    }
+}
+
+/// A single field marked `#[from]`, together with however it needs to be bound: `(source)` for a
+/// tuple shape, `{ field: source }` for a named one.
+struct FromField {
+    ty: syn::Type,
+    bind: TokenStream,
+}
+
+/// Finds the lone `#[from]`-marked field of a variant or struct, if any.
+///
+/// Panics if more than one field is marked `#[from]`, or if the marked field isn't the only
+/// field in its variant/struct (there would be no sensible way to fill in the rest).
+fn find_from_field(fields: &syn::Fields) -> Option<FromField> {
+    let (marked, len): (Vec<_>, usize) = match fields {
+        syn::Fields::Unit => return None,
+        syn::Fields::Unnamed(fields) => (
+            fields
+                .unnamed
+                .iter()
+                .filter(|field| find_attr(&field.attrs, "from").is_some())
+                .collect(),
+            fields.unnamed.len(),
+        ),
+        syn::Fields::Named(fields) => (
+            fields
+                .named
+                .iter()
+                .filter(|field| find_attr(&field.attrs, "from").is_some())
+                .collect(),
+            fields.named.len(),
+        ),
+    };
+
+    match marked.as_slice() {
+        [] => None,
+        [field] => {
+            if len != 1 {
+                panic!("#[from] is only supported on variants/structs with a single field");
+            }
+            let ty = field.ty.clone();
+            let bind = match &field.ident {
+                Some(name) => quote! { { #name: source } },
+                None => quote! { (source) },
+            };
+            Some(FromField { ty, bind })
+        }
+        _ => panic!("only one field may be marked #[from]"),
+    }
+}
+
+/// Finds the lone `#[source]`-marked field of a variant or struct, if any, returning the match
+/// pattern that binds it (and discards every other field): `(_, source)` for a tuple shape,
+/// `{ cause: source, .. }` for a named one. Unlike `#[from]`, `#[source]` doesn't generate a
+/// `From` impl and is allowed alongside other fields — it exists purely so a variant that
+/// doesn't want a `From` conversion can still plug into `Error::source()`.
+///
+/// Panics if more than one field is marked `#[source]`.
+fn find_source_field(fields: &syn::Fields) -> Option<TokenStream> {
+    match fields {
+        syn::Fields::Unit => None,
+        syn::Fields::Unnamed(fields) => {
+            let marked: Vec<_> = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| find_attr(&field.attrs, "source").is_some())
+                .collect();
+            match marked.as_slice() {
+                [] => None,
+                [(index, _)] => {
+                    let index = *index;
+                    let pattern = (0..fields.unnamed.len()).map(|i| {
+                        if i == index {
+                            quote! { source }
+                        } else {
+                            quote! { _ }
+                        }
+                    });
+                    Some(quote! { ( #(#pattern,)* ) })
                 }
-            },
-            syn::Fields::Unit => quote! {},
-        };
-        match attr {
-            Some(attr) => {
-                let status = attr.tokens;
-                quote! {
-                    Self::#variant_name #match_fields => {
-                        #[allow(unused_parens)]
-                        #status
-                    }
+                _ => panic!("only one field may be marked #[source]"),
+            }
+        }
+        syn::Fields::Named(fields) => {
+            let marked: Vec<_> = fields
+                .named
+                .iter()
+                .filter(|field| find_attr(&field.attrs, "source").is_some())
+                .collect();
+            match marked.as_slice() {
+                [] => None,
+                [field] => {
+                    let name = field.ident.as_ref().unwrap();
+                    Some(quote! { { #name: source, .. } })
                 }
-            },
-            None => {
-                quote! { Self::#variant_name #match_fields => ::axum::http::StatusCode::INTERNAL_SERVER_ERROR }
+                _ => panic!("only one field may be marked #[source]"),
+            }
+        }
+    }
+}
+
+/// Generates `impl From<Ty> for #ident` for a `#[from]` field, constructing through `ctor_path`
+/// (e.g. `Self::InsertUserToDb` or `Self`).
+fn from_impl(ident: &Ident, ctor_path: &TokenStream, from_field: &FromField) -> TokenStream {
+    let ty = &from_field.ty;
+    let bind = &from_field.bind;
+    quote! {
+        impl ::std::convert::From<#ty> for #ident {
+            fn from(source: #ty) -> Self {
+                #ctor_path #bind
+            }
+        }
+    }
+}
+
+/// Parses `#[status(...)]` off a variant or container, defaulting to a 500.
+fn status_expr_from_attrs(attrs: &[syn::Attribute]) -> TokenStream {
+    match find_attr(attrs, "status") {
+        Some(attr) => {
+            let status = &attr.tokens;
+            quote! {
+                #[allow(unused_parens)]
+                #status
+            }
+        }
+        None => quote! { ::axum::http::StatusCode::INTERNAL_SERVER_ERROR },
+    }
+}
+
+/// Parses the lone `#[public("...")]` literal off a variant or container, if present.
+fn public_lit_from_attrs(attrs: &[syn::Attribute]) -> Option<LitStr> {
+    find_attr(attrs, "public").map(|attr| {
+        attr.parse_args()
+            .expect("invalid #[public(...)] attribute, expected a string literal")
+    })
+}
+
+/// Binds every field of a variant/struct by name, so a `#[public("...")]` format string can
+/// refer to them: named fields keep their name, tuple fields are bound `field0`, `field1`, ...
+fn bind_fields_for(fields: &syn::Fields) -> TokenStream {
+    match fields {
+        syn::Fields::Named(named) => {
+            let names = named.named.iter().map(|field| field.ident.clone().unwrap());
+            quote! { { #(#names,)* } }
+        }
+        syn::Fields::Unnamed(unnamed) => {
+            let names = (0..unnamed.unnamed.len())
+                .map(|i| Ident::new(&format!("field{i}"), proc_macro2::Span::call_site()));
+            quote! { ( #(#names,)* ) }
+        }
+        syn::Fields::Unit => quote! {},
+    }
+}
+
+/// Builds the `Option<String>` expression for a `#[public(...)]` match arm: `None` if the
+/// variant/struct has no `#[public(...)]` literal, otherwise the formatted message.
+fn public_message_expr(lit: &Option<LitStr>) -> TokenStream {
+    match lit {
+        Some(lit) => quote! {
+            Some({
+                #![allow(unused_variables)]
+                format!(#lit)
+            })
+        },
+        None => quote! { None },
+    }
+}
+
+/// The `tracing` level (or `off`) a variant logs at before responding.
+#[derive(Clone, Copy)]
+enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn parse(value: &str) -> Self {
+        match value {
+            "off" => Self::Off,
+            "error" => Self::Error,
+            "warn" => Self::Warn,
+            "info" => Self::Info,
+            "debug" => Self::Debug,
+            "trace" => Self::Trace,
+            other => panic!(
+                "unsupported #[log(level = \"{other}\")], expected one of \
+                 off/error/warn/info/debug/trace"
+            ),
+        }
+    }
+
+    /// The fully-qualified `tracing` macro path for this level. Panics for `Off`, which is
+    /// handled by the caller before ever reaching here.
+    fn tracing_macro_path(self) -> TokenStream {
+        match self {
+            Self::Off => unreachable!("Off is handled before a macro path is needed"),
+            Self::Error => quote! { ::tracing::error },
+            Self::Warn => quote! { ::tracing::warn },
+            Self::Info => quote! { ::tracing::info },
+            Self::Debug => quote! { ::tracing::debug },
+            Self::Trace => quote! { ::tracing::trace },
+        }
+    }
+}
+
+/// Parsed `#[log(level = "...")]` variant/container attribute.
+struct LogAttr {
+    level: LitStr,
+}
+
+impl syn::parse::Parse for LogAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let metas = Punctuated::<MetaNameValue, Token![,]>::parse_terminated(input)?;
+        let mut level = None;
+        for meta in metas {
+            if meta.path.is_ident("level") {
+                if let syn::Lit::Str(lit) = meta.lit {
+                    level = Some(lit);
+                }
+            }
+        }
+        let level = level.ok_or_else(|| input.error("#[log(...)] requires a `level = \"...\"`"))?;
+        Ok(Self { level })
+    }
+}
+
+/// Parses `#[log(level = "...")]` off a variant or container, if present.
+fn log_level_from_attrs(attrs: &[syn::Attribute]) -> Option<LogLevel> {
+    let attr = find_attr(attrs, "log")?;
+    let parsed: LogAttr = attr.parse_args().expect("invalid #[log(...)] attribute");
+    Some(LogLevel::parse(&parsed.level.value()))
+}
+
+/// Generates the statement that logs a variant's error before responding. `resolved` is the
+/// variant's own `#[log(...)]` level if any, falling back to the container's. With neither, the
+/// original behaviour is preserved: log at `error` only for a 5xx, or whenever `#[public(...)]`
+/// suppressed detail the operator still needs to see.
+fn log_stmt_for(resolved: Option<LogLevel>) -> TokenStream {
+    match resolved {
+        Some(LogLevel::Off) => quote! {},
+        Some(level) => {
+            let tracing_macro = level.tracing_macro_path();
+            quote! {
+                #tracing_macro!(error_details = ?self, "{display_message}");
+            }
+        }
+        None => quote! {
+            if status.is_server_error() || public_message.is_some() {
+                ::tracing::error!(error_message = ?display_message, error_details = ?self, "internal server error");
+            }
+        },
+    }
+}
+
+/// Parsed `#[header("Name" = "value")]` per-variant/container attribute. Repeatable.
+struct HeaderAttr {
+    name: LitStr,
+    value: LitStr,
+}
+
+impl syn::parse::Parse for HeaderAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name: LitStr = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value: LitStr = input.parse()?;
+        Ok(Self { name, value })
+    }
+}
+
+/// Collects every `#[header("Name" = "value")]` attribute on a variant or container, in order.
+fn header_attrs_from(attrs: &[syn::Attribute]) -> Vec<HeaderAttr> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.get_ident().map(|i| *i == "header").unwrap_or(false))
+        .map(|attr| {
+            attr.parse_args()
+                .expect("invalid #[header(\"Name\" = \"value\")] attribute")
+        })
+        .collect()
+}
+
+/// Builds the `Vec<(HeaderName, String)>` expression for a variant/struct's `#[header(...)]`
+/// attributes, with the value literals allowed to interpolate the bound fields. A name that
+/// isn't a valid header name (e.g. it contains a space; ASCII case is normalized, not rejected)
+/// is dropped rather than panicking, the same as an invalid value is elsewhere.
+fn header_entries_expr(headers: &[HeaderAttr]) -> TokenStream {
+    let pushes = headers.iter().map(|header| {
+        let name = &header.name;
+        let value = &header.value;
+        quote! {
+            if let Ok(name) = ::axum::http::HeaderName::from_bytes(#name.as_bytes()) {
+                headers.push((name, format!(#value)));
             }
         }
     });
 
     quote! {
-        impl #ident {
-            fn status_code(&self) -> ::axum::http::StatusCode {
+        {
+            let mut headers: ::std::vec::Vec<(::axum::http::HeaderName, String)> =
+                ::std::vec::Vec::new();
+            #(#pushes)*
+            headers
+        }
+    }
+}
+
+/// Parses `#[problem(type = "...", title = "...")]` off a variant or container.
+fn problem_tuple_from_attrs(attrs: &[syn::Attribute]) -> TokenStream {
+    let problem: ProblemAttr = find_attr(attrs, "problem")
+        .map(|attr| attr.parse_args().expect("invalid #[problem(...)] attribute"))
+        .unwrap_or_default();
+
+    let type_uri = match problem.type_uri {
+        Some(lit) => quote! { #lit },
+        None => quote! { "about:blank" },
+    };
+    let title = match problem.title {
+        Some(lit) => quote! { #lit },
+        None => quote! { status.canonical_reason().unwrap_or("Error") },
+    };
+
+    quote! { (#type_uri, #title) }
+}
+
+fn derive_error_response_for_enum(
+    ident: Ident,
+    enum_data: DataEnum,
+    format: ResponseFormat,
+    container_log_level: Option<LogLevel>,
+) -> TokenStream {
+    let variants: Vec<_> = enum_data.variants.into_iter().collect();
+
+    let status_codes = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let match_fields = match_fields_for(&variant.fields);
+        let status = status_expr_from_attrs(&variant.attrs);
+        quote! { Self::#variant_name #match_fields => { #status } }
+    });
+
+    let problem_lookup = match format {
+        ResponseFormat::Default => quote! {},
+        ResponseFormat::ProblemJson => {
+            let problem_arms = variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let match_fields = match_fields_for(&variant.fields);
+                let problem_tuple = problem_tuple_from_attrs(&variant.attrs);
+                quote! { Self::#variant_name #match_fields => #problem_tuple }
+            });
+
+            quote! {
+                let (problem_type, problem_title): (&str, &str) = match &self {
+                    #(#problem_arms,)*
+                };
+            }
+        }
+    };
+
+    let status_code_body = quote! {
+        match self {
+            #(#status_codes,)*
+        }
+    };
+
+    let public_arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let public_lit = public_lit_from_attrs(&variant.attrs);
+        let pattern = match &public_lit {
+            Some(_) => bind_fields_for(&variant.fields),
+            None => match_fields_for(&variant.fields),
+        };
+        let expr = public_message_expr(&public_lit);
+        quote! { Self::#variant_name #pattern => #expr }
+    });
+    let public_lookup = quote! {
+        let public_message: Option<String> = match &self {
+            #(#public_arms,)*
+        };
+    };
+
+    let header_arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let headers = header_attrs_from(&variant.attrs);
+        let pattern = if headers.is_empty() {
+            match_fields_for(&variant.fields)
+        } else {
+            bind_fields_for(&variant.fields)
+        };
+        let entries = header_entries_expr(&headers);
+        quote! { Self::#variant_name #pattern => #entries }
+    });
+    let headers_lookup = quote! {
+        let response_headers: Vec<(::axum::http::HeaderName, String)> = match &self {
+            #(#header_arms,)*
+        };
+    };
+
+    let log_arms = variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let match_fields = match_fields_for(&variant.fields);
+        let resolved = log_level_from_attrs(&variant.attrs).or(container_log_level);
+        let log_stmt = log_stmt_for(resolved);
+        quote! { Self::#variant_name #match_fields => { #log_stmt } }
+    });
+    let log_lookup = quote! {
+        match &self {
+            #(#log_arms,)*
+        }
+    };
+
+    let mut from_impls = Vec::new();
+    let mut source_arms = Vec::new();
+    for variant in &variants {
+        let variant_name = &variant.ident;
+        let ctor_path = quote! { Self::#variant_name };
+        if let Some(from_field) = find_from_field(&variant.fields) {
+            from_impls.push(from_impl(&ident, &ctor_path, &from_field));
+            let bind = &from_field.bind;
+            source_arms.push(quote! { #ctor_path #bind => Some(source) });
+        } else if let Some(pattern) = find_source_field(&variant.fields) {
+            source_arms.push(quote! { #ctor_path #pattern => Some(source) });
+        }
+    }
+    let from_support = from_support_tokens(&ident, from_impls, source_arms, false);
+
+    render_impls(
+        &ident,
+        status_code_body,
+        RenderLookups {
+            problem_lookup,
+            public_lookup,
+            headers_lookup,
+            log_lookup,
+            from_support,
+        },
+        format,
+    )
+}
+
+fn derive_error_response_for_struct(
+    ident: Ident,
+    attrs: Vec<syn::Attribute>,
+    fields: syn::Fields,
+    format: ResponseFormat,
+    container_log_level: Option<LogLevel>,
+) -> TokenStream {
+    let status = status_expr_from_attrs(&attrs);
+    let status_code_body = quote! { #status };
+
+    let problem_lookup = match format {
+        ResponseFormat::Default => quote! {},
+        ResponseFormat::ProblemJson => {
+            let problem_tuple = problem_tuple_from_attrs(&attrs);
+            quote! {
+                let (problem_type, problem_title): (&str, &str) = #problem_tuple;
+            }
+        }
+    };
+
+    let public_lit = public_lit_from_attrs(&attrs);
+    let public_pattern = match &public_lit {
+        Some(_) => bind_fields_for(&fields),
+        None => match_fields_for(&fields),
+    };
+    let public_expr = public_message_expr(&public_lit);
+    let public_lookup = quote! {
+        let public_message: Option<String> = match &self {
+            Self #public_pattern => #public_expr,
+        };
+    };
+
+    let headers = header_attrs_from(&attrs);
+    let header_pattern = if headers.is_empty() {
+        match_fields_for(&fields)
+    } else {
+        bind_fields_for(&fields)
+    };
+    let header_entries = header_entries_expr(&headers);
+    let headers_lookup = quote! {
+        let response_headers: Vec<(::axum::http::HeaderName, String)> = match &self {
+            Self #header_pattern => #header_entries,
+        };
+    };
+
+    let log_stmt = log_stmt_for(container_log_level);
+    let log_lookup = quote! { #log_stmt };
+
+    let ctor_path = quote! { Self };
+    let from_support = if let Some(from_field) = find_from_field(&fields) {
+        let from_impl_tokens = from_impl(&ident, &ctor_path, &from_field);
+        let bind = &from_field.bind;
+        let source_arm = quote! { #ctor_path #bind => Some(source) };
+        from_support_tokens(&ident, vec![from_impl_tokens], vec![source_arm], true)
+    } else if let Some(pattern) = find_source_field(&fields) {
+        let source_arm = quote! { #ctor_path #pattern => Some(source) };
+        from_support_tokens(&ident, vec![], vec![source_arm], true)
+    } else {
+        quote! {}
+    };
+
+    render_impls(
+        &ident,
+        status_code_body,
+        RenderLookups {
+            problem_lookup,
+            public_lookup,
+            headers_lookup,
+            log_lookup,
+            from_support,
+        },
+        format,
+    )
+}
+
+/// Generates the `impl From<Ty>` for each `#[from]` field plus the `Error::source` impl that
+/// reads them (plus `#[source]` fields, which contribute a `source_arms` entry but no `From`
+/// impl), or nothing if there were none. `exhaustive` is `true` for a struct (its single arm
+/// already covers every value of `self`) and `false` for an enum (needs a wildcard for the
+/// variants with neither attribute).
+fn from_support_tokens(
+    ident: &Ident,
+    from_impls: Vec<TokenStream>,
+    source_arms: Vec<TokenStream>,
+    exhaustive: bool,
+) -> TokenStream {
+    if source_arms.is_empty() {
+        return quote! {};
+    }
+
+    let catch_all = if exhaustive {
+        quote! {}
+    } else {
+        quote! { _ => None, }
+    };
+
+    quote! {
+        #(#from_impls)*
+
+        impl ::std::error::Error for #ident {
+            fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
                 match self {
-                    #(#status_codes,)*
+                    #(#source_arms,)*
+                    #catch_all
                 }
             }
         }
+    }
+}
+
+/// The per-derive-path pieces [`render_impls`] stitches into the shared impls. `problem_lookup`
+/// (only used for [`ResponseFormat::ProblemJson`]) must bind `problem_type`/`problem_title:
+/// &str`; `public_lookup` must bind `public_message: Option<String>` from any `#[public(...)]`
+/// attribute; `headers_lookup` must bind `response_headers: Vec<(HeaderName, String)>` from any
+/// `#[header(...)]` attributes; `log_lookup` performs the `tracing` call (if any) per
+/// `#[log(...)]`; `from_support` holds any `#[from]`/`#[source]`-derived `Error` impl (or an
+/// empty stream).
+struct RenderLookups {
+    problem_lookup: TokenStream,
+    public_lookup: TokenStream,
+    headers_lookup: TokenStream,
+    log_lookup: TokenStream,
+    from_support: TokenStream,
+}
+
+/// Renders the `status_code`, `IntoResponse` and `Debug` impls shared by the struct and enum
+/// derive paths. `status_code_body` is the full body of `status_code`.
+fn render_impls(
+    ident: &Ident,
+    status_code_body: TokenStream,
+    lookups: RenderLookups,
+    format: ResponseFormat,
+) -> TokenStream {
+    let RenderLookups {
+        problem_lookup,
+        public_lookup,
+        headers_lookup,
+        log_lookup,
+        from_support,
+    } = lookups;
+    let body = match format {
+        ResponseFormat::Default => quote! {
+            let error_message =
+                client_message.unwrap_or_else(|| "Internal server error".to_string());
+
+            let body = ::axum::Json(::serde_json::json!({
+                "code": status.as_u16(),
+                "error": error_message,
+            }));
+
+            ::axum::response::IntoResponse::into_response((status, body))
+        },
+        ResponseFormat::ProblemJson => quote! {
+            #problem_lookup
+
+            let mut body = ::serde_json::json!({
+                "type": problem_type,
+                "title": problem_title,
+                "status": status.as_u16(),
+            });
+
+            if let Some(detail) = client_message {
+                body["detail"] = ::serde_json::Value::String(detail);
+            }
+
+            let mut response = ::axum::response::IntoResponse::into_response((
+                status,
+                ::axum::Json(body),
+            ));
+            response.headers_mut().insert(
+                ::axum::http::header::CONTENT_TYPE,
+                ::axum::http::HeaderValue::from_static("application/problem+json"),
+            );
+            response
+        },
+    };
+
+    quote! {
+        impl #ident {
+            fn status_code(&self) -> ::axum::http::StatusCode {
+                #status_code_body
+            }
+        }
 
+        #[allow(unused_variables)]
         impl ::axum::response::IntoResponse for #ident {
             fn into_response(self) -> ::axum::response::Response {
                 let status = self.status_code();
-                let mut error_message = self.to_string();
+                let display_message = self.to_string();
+                #public_lookup
+                #headers_lookup
 
-                if status.is_server_error() {
-                    ::tracing::error!(error_message, error_details = ?self, "internal server error");
-                    error_message = "Internal server error".to_string()
-                }
+                #log_lookup
 
-                let body = ::axum::Json(::serde_json::json!({
-                    "code": status.as_u16(),
-                    "error": error_message,
-                }));
+                let client_message = match public_message {
+                    Some(message) => Some(message),
+                    None if status.is_server_error() => None,
+                    None => Some(display_message),
+                };
 
-                ::axum::response::IntoResponse::into_response((status, body))
+                let mut response = { #body };
+
+                for (name, value) in response_headers {
+                    if let Ok(value) = ::axum::http::HeaderValue::from_str(&value) {
+                        response.headers_mut().insert(name, value);
+                    }
+                }
+
+                response
             }
         }
 
@@ -148,5 +1102,18 @@ fn derive_error_response_for_enum(ident: Ident, enum_data: DataEnum) -> TokenStr
                 Ok(())
             }
         }
+
+        #from_support
+    }
+}
+
+fn match_fields_for(fields: &syn::Fields) -> TokenStream {
+    match fields {
+        syn::Fields::Named(_) => quote!({ .. }),
+        syn::Fields::Unnamed(fields) => {
+            let fields = fields.unnamed.iter().map(|_| quote!(_));
+            quote! { (#(#fields,)*) }
+        }
+        syn::Fields::Unit => quote! {},
     }
 }